@@ -3,6 +3,9 @@
 //! The macro can be used exactly as `vec!`.
 
 #[cfg(feature="maybe-many")] pub mod maybe;
+pub mod sink;
+
+pub use sink::BackInserter;
 
 /// A bespoke iterator type with an exact size over elements.
 ///
@@ -21,7 +24,7 @@
 /// # Functions
 /// The iterators returned from this method have these associated functions:
 ///
-/// ## The length of the whole iterator
+/// ## The number of elements not yet yielded
 /// ```ignore
 /// pub const fn len(&self) -> usize
 /// ```
@@ -41,6 +44,17 @@
 /// ```ignore
 /// pub const fn consumed(&self) -> usize
 /// ```
+///
+/// ## Recover the backing array, consuming the iterator.
+/// Returns the array along with the `front` and `back` cursors (as used internally): slots `0..front` and `back..LEN` are uninitialised, slots `front..back` are initialised.
+/// ```ignore
+/// pub fn into_inner(self) -> ([MaybeUninit<T>; Self::LEN], usize, usize)
+/// ```
+///
+/// ## Drain the remaining elements into any [`BackInserter`](crate::BackInserter).
+/// ```ignore
+/// pub fn drain_into<B: BackInserter<T>>(self, sink: &mut B)
+/// ```
 #[macro_export] macro_rules! iter {
     (@) => (0usize);
     (@ $x:tt $($xs:tt)* ) => (1usize + $crate::iter!(@ $($xs)*));
@@ -73,28 +87,33 @@
 	{
 	    use ::std::mem::MaybeUninit;
 	    use ::std::ops::Drop;
-	    struct Arr<T>([MaybeUninit<T>; $crate::iter!(@ $($value)*)], usize);
+	    // Fields are (array, front, back): elements in `front..back` are initialised,
+	    // everything else has already been yielded from one end or the other.
+	    struct Arr<T>([MaybeUninit<T>; $crate::iter!(@ $($value)*)], usize, usize);
 	    impl<T> Arr<T>
 	    {
 		#![allow(dead_code)]
 		/// The length of the whole iterator
 		const LEN: usize = $crate::iter!(@ $($value)*);
 
-		/// The length of the whole iterator
-		// This exists as an associated function because this type is opaque.
+		/// The number of elements not yet yielded.
 		#[inline] pub const fn len(&self) -> usize
 		{
-		    Self::LEN
+		    self.2 - self.1
 		}
-		
-		/// Consume this iterator into the backing buffer.
+
+		/// Consume this iterator into the backing buffer, along with its `front` and `back` cursors.
 		///
 		/// # Safety
-		/// Non-consumed items are safe to `assume_init`. However, items that have already been consumed are uninitialised.
-		fn into_inner(self) -> [MaybeUninit<T>; $crate::iter!(@ $($value)*)]
+		/// Slots `front..back` are safe to `assume_init`. However, slots `0..front` and `back..LEN` have already been consumed and are uninitialised.
+		pub fn into_inner(self) -> ([MaybeUninit<T>; $crate::iter!(@ $($value)*)], usize, usize)
 		{
-		    //XXX: We will have to do something really unsafe for this to work on stable...
-		    todo!()
+		    let (front, back) = (self.1, self.2);
+		    // SAFETY: `self` is forgotten below, so its `Drop` impl never runs and
+		    // the bytes read out here are not also dropped in place by it.
+		    let array = unsafe { ::std::ptr::read(&self.0) };
+		    ::std::mem::forget(self);
+		    (array, front, back)
 		}
 
 		/// The rest of the iterator that has not been consumed.
@@ -103,9 +122,9 @@
 		// All values in this slice are initialised.
 		#[inline] pub fn rest(&self) -> &[T]
 		{
-		    let slice = &self.0[self.1..];
-		    //std::mem::MaybeUninit::slice_get_ref(&self.0[self.1..]) //nightly only...
-		    
+		    let slice = &self.0[self.1..self.2];
+		    //std::mem::MaybeUninit::slice_get_ref(&self.0[self.1..self.2]) //nightly only...
+
 		    unsafe { &*(slice as *const [std::mem::MaybeUninit<T>] as *const [T]) }
 		}
 
@@ -118,10 +137,19 @@
 		    &self.0
 		}
 
-		/// How many items have since been consumed.
+		/// How many items have since been consumed, from either end.
 		pub const fn consumed(&self) -> usize
 		{
-		    self.1
+		    self.1 + (Self::LEN - self.2)
+		}
+
+		/// Push every remaining element into `sink`, in order.
+		pub fn drain_into<B: $crate::BackInserter<T>>(mut self, sink: &mut B)
+		{
+		    sink.reserve(self.len());
+		    while let Some(value) = self.next() {
+			sink.push_back(value);
+		    }
 		}
 	    }
 	    impl<T> Iterator for Arr<T>
@@ -129,10 +157,10 @@
 		type Item = T;
 		fn next(&mut self) -> Option<Self::Item>
 		{
-		    if self.1 >= self.0.len() {
+		    if self.1 >= self.2 {
 			None
 		    } else {
-			//take one
+			//take one from the front
 			let one = unsafe {
 			    ::std::mem::replace(&mut self.0[self.1], MaybeUninit::uninit()).assume_init()
 			};
@@ -143,17 +171,34 @@
 
 		#[inline] fn size_hint(&self) -> (usize, Option<usize>)
 		{
-		    (Self::LEN, Some(Self::LEN))
+		    let len = self.len();
+		    (len, Some(len))
+		}
+	    }
+	    impl<T> ::std::iter::DoubleEndedIterator for Arr<T>
+	    {
+		fn next_back(&mut self) -> Option<Self::Item>
+		{
+		    if self.1 >= self.2 {
+			None
+		    } else {
+			//take one from the back
+			self.2-=1;
+			let one = unsafe {
+			    ::std::mem::replace(&mut self.0[self.2], MaybeUninit::uninit()).assume_init()
+			};
+			Some(one)
+		    }
 		}
 	    }
 	    impl<T> ::std::iter::FusedIterator for Arr<T>{}
 	    impl<T> ::std::iter::ExactSizeIterator for Arr<T>{}
-	    
+
 	    impl<T> Drop for Arr<T>
 	    {
 		fn drop(&mut self) {
 		    if ::std::mem::needs_drop::<T>() {
-			for idx in self.1..self.0.len() {
+			for idx in self.1..self.2 {
 			    unsafe {
 				::std::mem::replace(&mut self.0[idx], MaybeUninit::uninit()).assume_init();
 			    }
@@ -162,7 +207,7 @@
 		}
 	    }
 
-	    Arr([$(MaybeUninit::new($value)),*], 0)
+	    Arr([$(MaybeUninit::new($value)),*], 0, $crate::iter!(@ $($value)*))
 	}
     }
 }
@@ -219,4 +264,58 @@ mod tests
 	
 	assert_eq!(iter.rest().iter().map(|x| x.as_str()).collect::<Vec<_>>().as_slice(), &["world", "!"]);
     }
+
+    #[test]
+    fn rev()
+    {
+	const EXPECT: usize = 10 + 9 + 8 + 7 + 6 + 5 + 4 + 3 + 2 + 1;
+	let iter = iter![10,9,8,7,6,5,4,3,2,1];
+
+	assert_eq!(iter.rev().sum::<usize>(), EXPECT);
+    }
+
+    #[test]
+    fn double_ended_no_leak()
+    {
+	let mut iter = iter![string!("a"), string!("b"), string!("c"), string!("d")];
+
+	assert_eq!(iter.next(), Some(string!("a")));
+	assert_eq!(iter.next_back(), Some(string!("d")));
+	assert_eq!(iter.len(), 2);
+	assert_eq!(iter.next_back(), Some(string!("c")));
+	assert_eq!(iter.next(), Some(string!("b")));
+	assert_eq!(iter.next(), None);
+	assert_eq!(iter.next_back(), None);
+
+	// Partially drained from both ends and then dropped: the middle still holds
+	// "two" and "three", so `Drop` must drop those and not touch the consumed ends.
+	let mut iter = iter![string!("one"), string!("two"), string!("three"), string!("four")];
+	assert_eq!(iter.next(), Some(string!("one")));
+	assert_eq!(iter.next_back(), Some(string!("four")));
+    }
+
+    #[test]
+    fn into_inner()
+    {
+	let mut iter = iter![string!("a"), string!("b"), string!("c"), string!("d")];
+
+	assert_eq!(iter.next(), Some(string!("a")));
+	assert_eq!(iter.next(), Some(string!("b")));
+
+	let (mut array, front, back) = iter.into_inner();
+	assert_eq!((front, back), (2, 4));
+
+	let remaining: Vec<String> = (front..back).map(|i| unsafe {
+	    ::std::mem::replace(&mut array[i], ::std::mem::MaybeUninit::uninit()).assume_init()
+	}).collect();
+	assert_eq!(remaining, vec![string!("c"), string!("d")]);
+    }
+
+    #[test]
+    fn drain_into()
+    {
+	let mut sink = Vec::new();
+	iter![string!("a"), string!("b"), string!("c")].drain_into(&mut sink);
+	assert_eq!(sink, vec![string!("a"), string!("b"), string!("c")]);
+    }
 }
@@ -55,6 +55,81 @@ impl MaybeMany<std::convert::Infallible, std::iter::Empty<std::convert::Infallib
 	Self::None
     }
 }
+
+#[cfg(feature="smallvec")]
+impl<T, const N: usize> MaybeMany<T, smallvec::SmallVec<[T; N]>>
+{
+    /// Build a `Many` variant directly from an iterator of items, keeping up to `N` of them
+    /// inline before spilling to the heap.
+    ///
+    /// This does not collapse a 0- or 1-item `items` down to `None`/`One` — use
+    /// [`MaybeManyBuilder`] for that.
+    pub fn many_small<I>(items: I) -> Self
+    where I: IntoIterator<Item = T>
+    {
+	Self::Many(items.into_iter().collect())
+    }
+}
+
+/// Accumulates pushed items and collapses them into the smallest fitting [`MaybeMany`]
+/// variant: 0 items becomes `None`, 1 becomes `One`, 2 or more becomes an inline
+/// [`SmallVec`](smallvec::SmallVec) that only spills to the heap past `N` items.
+#[cfg(feature="smallvec")]
+#[derive(Debug, Clone)]
+pub struct MaybeManyBuilder<T, const N: usize>
+{
+    items: smallvec::SmallVec<[T; N]>,
+}
+
+#[cfg(feature="smallvec")]
+impl<T, const N: usize> MaybeManyBuilder<T, N>
+{
+    /// Create an empty builder.
+    #[inline] pub fn new() -> Self
+    {
+	Self { items: smallvec::SmallVec::new() }
+    }
+
+    /// Push another item onto the end.
+    #[inline] pub fn push(&mut self, value: T)
+    {
+	self.items.push(value);
+    }
+
+    /// Collapse the accumulated items into a `MaybeMany`.
+    pub fn build(mut self) -> MaybeMany<T, smallvec::SmallVec<[T; N]>>
+    {
+	match self.items.len() {
+	    0 => MaybeMany::None,
+	    1 => MaybeMany::One(self.items.pop().expect("checked len() == 1 above")),
+	    _ => MaybeMany::Many(self.items),
+	}
+    }
+}
+
+#[cfg(feature="smallvec")]
+impl<T, const N: usize> Default for MaybeManyBuilder<T, N>
+{
+    #[inline] fn default() -> Self
+    {
+	Self::new()
+    }
+}
+
+#[cfg(feature="smallvec")]
+impl<T, const N: usize> crate::BackInserter<T> for MaybeManyBuilder<T, N>
+{
+    #[inline] fn push_back(&mut self, value: T)
+    {
+	self.push(value);
+    }
+
+    #[inline] fn reserve(&mut self, additional: usize)
+    {
+	self.items.reserve(additional);
+    }
+}
+
 impl<T,U> MaybeMany<T,U>
 where U: IntoIterator<Item = T>
 
@@ -174,6 +249,264 @@ where U: IntoIterator<Item = T>
     {
 	std::mem::replace(self, Self::None)
     }
+
+    /// Push every yielded element into `sink`, in order.
+    ///
+    /// Reserves capacity in `sink` up front when [`size_hint()`](Self::size_hint) is known.
+    pub fn drain_into<B: crate::BackInserter<T>>(self, sink: &mut B)
+    {
+	if let Some(n) = self.size_hint() {
+	    sink.reserve(n);
+	}
+	for value in self {
+	    sink.push_back(value);
+	}
+    }
+}
+
+impl<T> MaybeMany<T, Vec<T>>
+{
+    /// Eagerly build from a fallible iterator, short-circuiting on the first `Err`.
+    ///
+    /// Collapses to `None`/`One`/`Many` based on the number of successful items, mirroring
+    /// the usual `0/1/many` collapse but abandoning construction the moment an item fails.
+    pub fn try_collect<I, E>(items: I) -> Result<Self, E>
+    where I: IntoIterator<Item = Result<T, E>>
+    {
+	let mut buf = Vec::new();
+	for item in items {
+	    buf.push(item?);
+	}
+	Ok(match buf.len() {
+	    0 => Self::None,
+	    1 => Self::One(buf.pop().expect("checked len() == 1 above")),
+	    _ => Self::Many(buf),
+	})
+    }
+}
+
+/// Yields items from a fallible iterator until the first `Err`, like `Iterator::map_while`,
+/// stashing the error instead of discarding it.
+#[derive(Debug, Clone)]
+pub struct TryIter<I, E>
+{
+    iter: I,
+    err: Option<E>,
+}
+
+impl<I, T, E> TryIter<I, E>
+where I: Iterator<Item = Result<T, E>>
+{
+    /// Wrap a fallible iterator so it stops yielding at the first error.
+    #[inline] pub fn new(iter: I) -> Self
+    {
+	Self { iter, err: None }
+    }
+
+    /// Take the stashed error, if the wrapped iterator has produced one.
+    #[inline] pub fn take_err(&mut self) -> Option<E>
+    {
+	self.err.take()
+    }
+}
+
+impl<I, T, E> Iterator for TryIter<I, E>
+where I: Iterator<Item = Result<T, E>>
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item>
+    {
+	if self.err.is_some() {
+	    return None;
+	}
+	match self.iter.next() {
+	    Some(Ok(value)) => Some(value),
+	    Some(Err(e)) => {
+		self.err = Some(e);
+		None
+	    },
+	    None => None,
+	}
+    }
+}
+impl<I, T, E> std::iter::FusedIterator for TryIter<I, E>
+where I: Iterator<Item = Result<T, E>>{}
+
+impl<T, I, E> MaybeMany<T, TryIter<I, E>>
+where I: Iterator<Item = Result<T, E>>
+{
+    /// Lazily build a `Many` variant from a fallible iterator.
+    ///
+    /// Unlike [`try_collect`](MaybeMany::try_collect), this does not buffer items or decide
+    /// between `None`/`One` up front: it always yields `Many`, wrapping a [`TryIter`] that
+    /// stops at the first error. Call [`take_err`](Self::take_err) after draining to recover
+    /// the error, if any was encountered.
+    pub fn try_many_lazy(items: I) -> Self
+    {
+	Self::Many(TryIter::new(items))
+    }
+
+    /// Retrieve the error stashed by the wrapped [`TryIter`], if any.
+    pub fn take_err(&mut self) -> Option<E>
+    {
+	match self {
+	    Self::Many(iter) => iter.take_err(),
+	    _ => None,
+	}
+    }
+}
+
+/// Lazily flattens an iterator of `IntoIterator`s, like `core::iter::Flatten`.
+pub struct Flatten<O>
+where O: Iterator,
+      O::Item: IntoIterator,
+{
+    outer: O,
+    front: Option<<O::Item as IntoIterator>::IntoIter>,
+}
+
+impl<O> Flatten<O>
+where O: Iterator,
+      O::Item: IntoIterator,
+{
+    pub(crate) fn new(outer: O) -> Self
+    {
+	Self { outer, front: None }
+    }
+}
+
+impl<O> Clone for Flatten<O>
+where O: Iterator + Clone,
+      O::Item: IntoIterator,
+      <O::Item as IntoIterator>::IntoIter: Clone,
+{
+    fn clone(&self) -> Self
+    {
+	Self { outer: self.outer.clone(), front: self.front.clone() }
+    }
+}
+
+impl<O> std::fmt::Debug for Flatten<O>
+where O: Iterator + std::fmt::Debug,
+      O::Item: IntoIterator,
+      <O::Item as IntoIterator>::IntoIter: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+	f.debug_struct("Flatten")
+	    .field("outer", &self.outer)
+	    .field("front", &self.front)
+	    .finish()
+    }
+}
+
+impl<O> Iterator for Flatten<O>
+where O: Iterator,
+      O::Item: IntoIterator,
+{
+    type Item = <O::Item as IntoIterator>::Item;
+    fn next(&mut self) -> Option<Self::Item>
+    {
+	loop {
+	    if let Some(front) = &mut self.front {
+		if let Some(item) = front.next() {
+		    return Some(item);
+		}
+	    }
+	    self.front = Some(self.outer.next()?.into_iter());
+	}
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+	let front = self.front.as_ref().map(Iterator::size_hint).unwrap_or((0, Some(0)));
+	if self.outer.size_hint() == (0, Some(0)) {
+	    // No more outer items to pull in: the front iterator's own hint is exact.
+	    front
+	} else {
+	    (front.0, None)
+	}
+    }
+}
+
+/// Either pass an already-flat iterator straight through, or flatten lazily.
+///
+/// This is the backing iterator of [`MaybeMany::flatten`]/[`MaybeMany::flatten_any`]: the
+/// `Direct` case avoids wrapping an inner iterator that doesn't need any further flattening.
+#[derive(Debug, Clone)]
+pub enum FlattenMany<A, B>
+{
+    Direct(A),
+    Lazy(B),
+}
+
+impl<A, B, T> Iterator for FlattenMany<A, B>
+where A: Iterator<Item = T>,
+      B: Iterator<Item = T>,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item>
+    {
+	match self {
+	    Self::Direct(a) => a.next(),
+	    Self::Lazy(b) => b.next(),
+	}
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+	match self {
+	    Self::Direct(a) => a.size_hint(),
+	    Self::Lazy(b) => b.size_hint(),
+	}
+    }
+}
+
+impl<T, V, U> MaybeMany<MaybeMany<T, V>, U>
+where V: IntoIterator<Item = T>,
+      U: IntoIterator<Item = MaybeMany<T, V>>,
+{
+    /// Flatten a `MaybeMany` of `MaybeMany`s into a single `MaybeMany`, without heap
+    /// allocation in the common small cases.
+    ///
+    /// * `None` collapses to `None`.
+    /// * `One(inner)` passes `inner` straight through — it's already flat, so it's not
+    ///   wrapped in the lazy adapter below.
+    /// * `Many(outer)` flattens lazily, like `core`'s `Flatten` adapter. Its `size_hint` only
+    ///   reports an upper bound once `outer`'s own `size_hint` is `(0, Some(0))` —
+    ///   until then the upper bound is unknown, since later inner iterators haven't been seen.
+    pub fn flatten(self) -> MaybeMany<T, FlattenMany<V::IntoIter, Flatten<U::IntoIter>>>
+    {
+	match self {
+	    Self::None => MaybeMany::None,
+	    Self::One(inner) => match inner {
+		MaybeMany::None => MaybeMany::None,
+		MaybeMany::One(t) => MaybeMany::One(t),
+		MaybeMany::Many(v) => MaybeMany::Many(FlattenMany::Direct(v.into_iter())),
+	    },
+	    Self::Many(outer) => MaybeMany::Many(FlattenMany::Lazy(Flatten::new(outer.into_iter()))),
+	}
+    }
+}
+
+impl<T, W, U> MaybeMany<W, U>
+where W: IntoIterator<Item = T>,
+      U: IntoIterator<Item = W>,
+{
+    /// Flatten a `MaybeMany` whose items are any `IntoIterator`, analogous to
+    /// [`flatten`](Self::flatten) but for items that aren't necessarily themselves a
+    /// `MaybeMany`.
+    ///
+    /// Since a single inner item's element count isn't known ahead of time, this always
+    /// collapses to `Many` (or `None` if the outer was `None`) rather than `One`.
+    pub fn flatten_any(self) -> MaybeMany<T, FlattenMany<W::IntoIter, Flatten<U::IntoIter>>>
+    {
+	match self {
+	    Self::None => MaybeMany::None,
+	    Self::One(w) => MaybeMany::Many(FlattenMany::Direct(w.into_iter())),
+	    Self::Many(outer) => MaybeMany::Many(FlattenMany::Lazy(Flatten::new(outer.into_iter()))),
+	}
+    }
 }
 
 /// An iterator for `MaybeMany` instances.
@@ -207,6 +540,18 @@ where U: Iterator<Item=T>
 	}
     }
 }
+impl<T,U> std::iter::DoubleEndedIterator for MaybeManyIter<T,U>
+where U: Iterator<Item=T> + DoubleEndedIterator
+{
+    fn next_back(&mut self) -> Option<Self::Item>
+    {
+	match self {
+	    Self::None => None,
+	    Self::One(one) => one.next_back(),
+	    Self::Many(many) => many.next_back(),
+	}
+    }
+}
 impl<T,U: Iterator<Item=T>> std::iter::FusedIterator for MaybeManyIter<T,U>{}
 impl<T,U: Iterator<Item=T>> std::iter::ExactSizeIterator for MaybeManyIter<T,U>
 where U: ExactSizeIterator{}
@@ -240,4 +585,149 @@ mod tests
 
 	assert_eq!(&output[..], &["hello", " ", "world", "!"]);
     }
+
+    #[test]
+    fn double_ended_no_leak()
+    {
+	let string = |s: &str| s.to_string();
+
+	let mut iter = MaybeMany::Many(vec![string("a"), string("b"), string("c"), string("d")]).into_iter();
+	assert_eq!(iter.next(), Some(string("a")));
+	assert_eq!(iter.next_back(), Some(string("d")));
+	assert_eq!(iter.next_back(), Some(string("c")));
+	assert_eq!(iter.next(), Some(string("b")));
+	assert_eq!(iter.next(), None);
+	assert_eq!(iter.next_back(), None);
+
+	// `One` is backed by `std::iter::Once`, a distinct code path from `Many`'s
+	// `Fuse`-wrapped inner iterator: `next_back` must yield the value exactly once too.
+	let mut iter = MaybeMany::one(string("only")).into_iter();
+	assert_eq!(iter.next_back(), Some(string("only")));
+	assert_eq!(iter.next_back(), None);
+	assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn drain_into()
+    {
+	let mut sink = Vec::new();
+	MaybeMany::<_, Vec<_>>::None.drain_into(&mut sink);
+	assert!(sink.is_empty());
+
+	MaybeMany::one("hello").drain_into(&mut sink);
+	MaybeMany::Many(vec!["world", "!"]).drain_into(&mut sink);
+
+	assert_eq!(&sink[..], &["hello", "world", "!"]);
+    }
+
+    #[cfg(feature="smallvec")]
+    #[test]
+    fn many_small()
+    {
+	let mayb: MaybeMany<_, smallvec::SmallVec<[_; 2]>> = MaybeMany::many_small(vec![1,2,3]);
+	assert!(mayb.is_many());
+	assert_eq!(mayb.into_iter().collect::<Vec<_>>(), vec![1,2,3]);
+    }
+
+    #[cfg(feature="smallvec")]
+    #[test]
+    fn builder_collapses()
+    {
+	let mut builder = MaybeManyBuilder::<_, 4>::new();
+	assert!(builder.clone().build().is_none());
+
+	builder.push("one");
+	assert!(matches!(builder.clone().build(), MaybeMany::One("one")));
+
+	builder.push("two");
+	assert!(builder.build().is_many());
+    }
+
+    #[cfg(feature="smallvec")]
+    #[test]
+    fn unusual_inline_capacity()
+    {
+	// `N` values off smallvec's hardcoded allow-list only compile with the
+	// `const_generics` feature enabled on the `smallvec` dependency.
+	let mayb: MaybeMany<_, smallvec::SmallVec<[_; 40]>> = MaybeMany::many_small(1..=3);
+	assert!(mayb.is_many());
+	assert_eq!(mayb.into_iter().collect::<Vec<_>>(), vec![1,2,3]);
+
+	let mut builder = MaybeManyBuilder::<_, 40>::new();
+	builder.push(1);
+	builder.push(2);
+	assert!(builder.build().is_many());
+    }
+
+    #[test]
+    fn try_collect_all_ok()
+    {
+	let items: Vec<Result<i32, &str>> = vec![Ok(1), Ok(2), Ok(3)];
+	let mayb = MaybeMany::try_collect(items).unwrap();
+	assert_eq!(mayb.into_iter().collect::<Vec<_>>(), vec![1,2,3]);
+    }
+
+    #[test]
+    fn try_collect_empty()
+    {
+	let items: Vec<Result<i32, &str>> = vec![];
+	let mayb = MaybeMany::try_collect(items).unwrap();
+	assert!(mayb.is_none());
+    }
+
+    #[test]
+    fn try_collect_mid_stream_error()
+    {
+	let items: Vec<Result<i32, &str>> = vec![Ok(1), Ok(2), Err("bad"), Ok(3)];
+	let err = MaybeMany::try_collect(items).unwrap_err();
+	assert_eq!(err, "bad");
+    }
+
+    #[test]
+    fn try_many_lazy_stops_at_error()
+    {
+	let items: Vec<Result<i32, &str>> = vec![Ok(1), Ok(2), Err("bad"), Ok(3)];
+	let mut mayb = MaybeMany::try_many_lazy(items.into_iter());
+
+	let collected: Vec<_> = match &mut mayb {
+	    MaybeMany::Many(iter) => iter.collect(),
+	    _ => unreachable!(),
+	};
+	assert_eq!(collected, vec![1,2]);
+	assert_eq!(mayb.take_err(), Some("bad"));
+    }
+
+    #[test]
+    fn flatten_none()
+    {
+	let outer: MaybeMany<MaybeMany<i32, Vec<i32>>, Vec<_>> = MaybeMany::None;
+	assert!(outer.flatten().is_none());
+    }
+
+    #[test]
+    fn flatten_one_passes_through()
+    {
+	let outer: MaybeMany<MaybeMany<i32, Vec<i32>>, Vec<_>> = MaybeMany::One(MaybeMany::One(5));
+	assert!(matches!(outer.flatten(), MaybeMany::One(5)));
+    }
+
+    #[test]
+    fn flatten_many()
+    {
+	let outer: MaybeMany<MaybeMany<i32, Vec<i32>>, Vec<_>> = MaybeMany::Many(vec![
+	    MaybeMany::One(1),
+	    MaybeMany::None,
+	    MaybeMany::Many(vec![2,3]),
+	]);
+	assert_eq!(outer.flatten().into_iter().collect::<Vec<_>>(), vec![1,2,3]);
+    }
+
+    #[test]
+    fn flatten_any_collapses_one_to_many()
+    {
+	let outer: MaybeMany<Vec<i32>, Vec<_>> = MaybeMany::One(vec![1,2,3]);
+	let flat = outer.flatten_any();
+	assert!(flat.is_many());
+	assert_eq!(flat.into_iter().collect::<Vec<_>>(), vec![1,2,3]);
+    }
 }
@@ -0,0 +1,95 @@
+//! A trait for funnelling iterator output into arbitrary containers.
+//!
+//! This exists so the `0/1/many` iterators in this crate don't force callers into
+//! `collect::<Vec<_>>()` and its allocation when they already have somewhere to put the values.
+
+/// A container that values can be pushed onto the back of.
+pub trait BackInserter<T>
+{
+    /// Push `value` onto the back of this container.
+    fn push_back(&mut self, value: T);
+
+    /// Hint that `additional` more elements are about to be pushed.
+    ///
+    /// The default implementation does nothing; containers backed by a growable buffer
+    /// should override this to pre-allocate and avoid repeated reallocation.
+    #[inline] fn reserve(&mut self, additional: usize)
+    {
+	let _ = additional;
+    }
+}
+
+impl<T> BackInserter<T> for Vec<T>
+{
+    #[inline] fn push_back(&mut self, value: T)
+    {
+	self.push(value);
+    }
+
+    #[inline] fn reserve(&mut self, additional: usize)
+    {
+	Vec::reserve(self, additional);
+    }
+}
+
+#[cfg(feature="smallvec")]
+impl<T, const N: usize> BackInserter<T> for smallvec::SmallVec<[T; N]>
+where [T; N]: smallvec::Array<Item = T>
+{
+    #[inline] fn push_back(&mut self, value: T)
+    {
+	self.push(value);
+    }
+
+    #[inline] fn reserve(&mut self, additional: usize)
+    {
+	smallvec::SmallVec::reserve(self, additional);
+    }
+}
+
+/// Adapts a `FnMut(T)` closure into a [`BackInserter<T>`].
+pub struct FnSink<F>(F);
+
+impl<F> FnSink<F>
+{
+    /// Wrap `fun` as a `BackInserter`.
+    #[inline] pub fn new(fun: F) -> Self
+    {
+	Self(fun)
+    }
+}
+
+impl<T, F> BackInserter<T> for FnSink<F>
+where F: FnMut(T)
+{
+    #[inline] fn push_back(&mut self, value: T)
+    {
+	(self.0)(value)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn vec_sink()
+    {
+	let mut v = Vec::new();
+	v.push_back(1);
+	v.push_back(2);
+	assert_eq!(v, vec![1,2]);
+    }
+
+    #[test]
+    fn fn_sink()
+    {
+	let mut sum = 0;
+	let mut sink = FnSink::new(|x: i32| sum += x);
+	sink.push_back(1);
+	sink.push_back(2);
+	sink.push_back(3);
+	assert_eq!(sum, 6);
+    }
+}